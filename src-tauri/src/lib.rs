@@ -1,11 +1,18 @@
+mod phash;
+mod upload;
+
+use aws_sdk_s3::Client as S3Client;
+use phash::BkTree;
 use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher, Event, EventKind};
+use ignore::overrides::{Override, OverrideBuilder};
+use ignore::{WalkBuilder, WalkState};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
-use std::time::{Duration, Instant};
-use tauri::{AppHandle, Emitter};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager};
 use uuid::Uuid;
 use chrono::Local;
 
@@ -14,38 +21,89 @@ const UPLOAD_COMPLETE_TIMEOUT: u64 = 30;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
     pub watch_folder: String,
-    pub file_types: Vec<String>,
+    /// Gitignore-style include/exclude globs. A bare glob whitelists matching
+    /// files; a `!`-prefixed glob excludes them. When any include is present,
+    /// files matching no include are skipped. Compiled into an [`Override`]
+    /// matcher in [`GlobalState`].
+    pub rules: Vec<String>,
     pub watch_subdirs: bool,
     pub sound_enabled: bool,
-    pub ignore_folders: Vec<String>,
     pub save_history: bool,
+    /// Max Hamming distance (0–20) at which two video fingerprints count as the
+    /// same footage for duplicate detection.
+    pub duplicate_tolerance: u32,
+    /// S3-compatible object storage for archiving signed batches. Leave
+    /// `s3_bucket` empty to disable cloud upload entirely.
+    pub s3_endpoint: String,
+    pub s3_region: String,
+    pub s3_bucket: String,
+    pub s3_prefix: String,
+    pub s3_access_key: String,
+    pub s3_secret_key: String,
+    /// Seconds between completion poll cycles.
+    pub poll_interval_secs: u64,
+    /// Consecutive poll cycles a folder's files must keep the same size and
+    /// mtime before the batch is considered settled and marked `Completed`.
+    pub stability_cycles: u32,
 }
 
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
             watch_folder: String::new(),
-            file_types: vec![
-                ".mp4".into(), ".avi".into(), ".mkv".into(), ".mov".into(),
-                ".wmv".into(), ".flv".into(), ".webm".into(), ".m4v".into(),
-                ".mpeg".into(), ".mpg".into(), ".3gp".into(), ".ts".into(),
+            rules: vec![
+                "**/*.mp4".into(), "**/*.avi".into(), "**/*.mkv".into(), "**/*.mov".into(),
+                "**/*.wmv".into(), "**/*.flv".into(), "**/*.webm".into(), "**/*.m4v".into(),
+                "**/*.mpeg".into(), "**/*.mpg".into(), "**/*.3gp".into(), "**/*.ts".into(),
+                // Exclude both the directory itself and its contents: the bare
+                // `dir/` form lets the baseline walk prune the whole subtree,
+                // while `dir/**` keeps any stray events inside it filtered.
+                "!**/node_modules/".into(), "!**/node_modules/**".into(),
+                "!**/.git/".into(), "!**/.git/**".into(),
+                "!**/__pycache__/".into(), "!**/__pycache__/**".into(),
+                "!**/.idea/".into(), "!**/.idea/**".into(),
+                "!**/vendor/".into(), "!**/vendor/**".into(),
+                "!**/target/".into(), "!**/target/**".into(),
             ],
             watch_subdirs: true,
             sound_enabled: true,
-            ignore_folders: vec![
-                "node_modules".into(), ".git".into(), "__pycache__".into(),
-                ".idea".into(), "vendor".into(), "target".into(),
-            ],
             save_history: true,
+            duplicate_tolerance: 10,
+            s3_endpoint: String::new(),
+            s3_region: String::new(),
+            s3_bucket: String::new(),
+            s3_prefix: String::new(),
+            s3_access_key: String::new(),
+            s3_secret_key: String::new(),
+            poll_interval_secs: 2,
+            stability_cycles: 3,
         }
     }
 }
 
+/// Compile the config's [`AppConfig::rules`] into an [`Override`] matcher rooted
+/// at the watch folder. Invalid globs are skipped so a single bad rule can't
+/// break monitoring; an empty rule set yields an empty matcher that accepts
+/// everything.
+fn build_overrides(config: &AppConfig) -> Override {
+    let root = if config.watch_folder.is_empty() {
+        PathBuf::from(".")
+    } else {
+        PathBuf::from(&config.watch_folder)
+    };
+    let mut builder = OverrideBuilder::new(&root);
+    for rule in &config.rules {
+        builder.add(rule).ok();
+    }
+    builder.build().unwrap_or_else(|_| Override::empty())
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum BatchStatus {
     Uploading,
     Completed,
     Signed,
+    Uploaded,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -57,6 +115,7 @@ pub struct Batch {
     pub started_at: String,
     pub completed_at: Option<String>,
     pub signed_at: Option<String>,
+    pub uploaded_at: Option<String>,
     pub file_count: usize,
 }
 
@@ -76,28 +135,55 @@ struct FolderTracker {
     started_at: String,
     notified_start: bool,
     batch_id: String,
+    /// Last observed `(size, mtime)` per file, used to detect write-settling.
+    file_stats: HashMap<String, (u64, i64)>,
+    /// Consecutive poll cycles the file stats have been unchanged.
+    stable_cycles: u32,
+    /// Value of `last_activity` as of the previous poll, to tell whether any
+    /// new events arrived between cycles.
+    activity_at_last_poll: Instant,
+}
+
+/// A `Modify(Name(From))` awaiting its matching `Modify(Name(To))`. Split
+/// renames on Linux arrive as two events sharing a tracker cookie; holding the
+/// `From` side briefly lets us swap the name in place instead of tearing the
+/// batch down and rebuilding it with a fresh id.
+struct PendingRename {
+    folder: String,
+    name: String,
+    at: Instant,
 }
 
 pub struct GlobalState {
     pub config: AppConfig,
+    pub overrides: Override,
+    pub dup_index: BkTree,
+    pub s3_client: Option<S3Client>,
     pub batches: Vec<Batch>,
     pub folder_trackers: HashMap<String, FolderTracker>,
+    pub pending_renames: HashMap<usize, PendingRename>,
     pub is_running: bool,
     pub dir_count: i32,
     pub start_time: String,
     pub watcher: Option<RecommendedWatcher>,
+    pub config_watcher: Option<RecommendedWatcher>,
 }
 
 impl Default for GlobalState {
     fn default() -> Self {
         Self {
             config: AppConfig::default(),
+            overrides: Override::empty(),
+            dup_index: BkTree::new(),
+            s3_client: None,
             batches: Vec::new(),
             folder_trackers: HashMap::new(),
+            pending_renames: HashMap::new(),
             is_running: false,
             dir_count: 0,
             start_time: String::new(),
             watcher: None,
+            config_watcher: None,
         }
     }
 }
@@ -147,6 +233,89 @@ fn save_history(batches: &[Batch]) {
     }
 }
 
+/// Watch the config file's directory so edits made on disk (or by another
+/// process) take effect without a restart. Returns the watcher to keep alive.
+fn start_config_watcher(state: SharedState, app: AppHandle) -> Option<RecommendedWatcher> {
+    let config_path = get_config_path();
+    let dir = config_path.parent()?.to_path_buf();
+
+    let state_cb = Arc::clone(&state);
+    let app_cb = app.clone();
+    let target = config_path.clone();
+    let mut watcher = RecommendedWatcher::new(
+        move |res: Result<Event, notify::Error>| {
+            if let Ok(event) = res {
+                let touches_config = matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+                    && event.paths.iter().any(|p| p == &target);
+                if touches_config {
+                    reload_config(&state_cb, &app_cb);
+                }
+            }
+        },
+        Config::default(),
+    ).ok()?;
+
+    watcher.watch(&dir, RecursiveMode::NonRecursive).ok()?;
+    Some(watcher)
+}
+
+/// Re-read the config from disk, validate it, and apply it live. A parse error
+/// is ignored so a partial write can't clobber the running config. If the watch
+/// folder, recursion mode, or filter rules changed while monitoring, the file
+/// watcher is rebuilt in place, preserving existing trackers and batches.
+fn reload_config(state: &SharedState, app: &AppHandle) {
+    let data = match fs::read_to_string(get_config_path()) {
+        Ok(d) => d,
+        Err(_) => return,
+    };
+    let new_config: AppConfig = match serde_json::from_str(&data) {
+        Ok(c) => c,
+        Err(_) => return,
+    };
+
+    let mut s = state.lock().unwrap();
+    let old = s.config.clone();
+    let watch_changed = old.watch_folder != new_config.watch_folder
+        || old.watch_subdirs != new_config.watch_subdirs
+        || old.rules != new_config.rules;
+
+    s.overrides = build_overrides(&new_config);
+    s.s3_client = None;
+    s.config = new_config.clone();
+
+    let mut monitor_stopped = false;
+    if s.is_running && watch_changed {
+        // Rebuild the file watcher against the new folder/rules without
+        // discarding in-flight trackers or recorded batches.
+        let folder = new_config.watch_folder.clone();
+        let rebuilt = if !folder.is_empty() && PathBuf::from(&folder).exists() {
+            create_watcher(state, app, &folder, new_config.watch_subdirs)
+        } else {
+            None
+        };
+        match rebuilt {
+            Some(w) => s.watcher = Some(w),
+            // The new folder is missing/inaccessible: stop monitoring cleanly
+            // and tell the UI rather than silently running without a watcher.
+            None => {
+                s.watcher = None;
+                s.is_running = false;
+                s.start_time.clear();
+                s.dir_count = 0;
+                s.folder_trackers.clear();
+                s.pending_renames.clear();
+                monitor_stopped = true;
+            }
+        }
+    }
+    drop(s);
+
+    if monitor_stopped {
+        app.emit("monitor-stopped", ()).ok();
+    }
+    app.emit("config-reloaded", &new_config).ok();
+}
+
 #[tauri::command]
 fn get_config(state: tauri::State<SharedState>) -> AppConfig {
     state.lock().unwrap().config.clone()
@@ -155,6 +324,9 @@ fn get_config(state: tauri::State<SharedState>) -> AppConfig {
 #[tauri::command]
 fn set_config(state: tauri::State<SharedState>, config: AppConfig) {
     let mut s = state.lock().unwrap();
+    s.overrides = build_overrides(&config);
+    // Drop the cached client so new credentials/endpoint take effect.
+    s.s3_client = None;
     s.config = config.clone();
     save_config(&config);
 }
@@ -183,39 +355,55 @@ fn start_monitor(app: AppHandle, state: tauri::State<SharedState>) -> bool {
         return false;
     }
     
-    let config = s.config.clone();
-    let state_clone = Arc::clone(&state.inner());
-    let app_handle = app.clone();
-    
-    let watcher = RecommendedWatcher::new(
-        move |res: Result<Event, notify::Error>| {
-            if let Ok(event) = res {
-                handle_file_event(event, &state_clone, &config, &app_handle);
-            }
-        },
-        Config::default(),
-    );
-    
-    match watcher {
-        Ok(mut w) => {
-            let mode = if s.config.watch_subdirs { RecursiveMode::Recursive } else { RecursiveMode::NonRecursive };
-            if w.watch(watch_folder.as_ref(), mode).is_err() { return false; }
-            
+    s.overrides = build_overrides(&s.config);
+
+    let shared = Arc::clone(state.inner());
+    match create_watcher(&shared, &app, &watch_folder, s.config.watch_subdirs) {
+        Some(w) => {
             s.watcher = Some(w);
             s.is_running = true;
             s.start_time = Local::now().format("%H:%M:%S").to_string();
-            s.dir_count = 1;
-            
+            s.dir_count = 0;
+
+            // Enumerate what's already on disk before the watcher attached, so
+            // pre-existing files and in-flight uploads are tracked and the
+            // directory count is real. Runs off-thread and streams to the UI.
+            let recursive = s.config.watch_subdirs;
+            let overrides = s.overrides.clone();
+            let state_for_scan = Arc::clone(&state.inner());
+            let app_for_scan = app.clone();
+            let scan_root = watch_folder.clone();
+            std::thread::spawn(move || baseline_scan(state_for_scan, app_for_scan, scan_root, overrides, recursive));
+
             let state_for_checker = Arc::clone(&state.inner());
             let app_for_checker = app.clone();
             std::thread::spawn(move || completion_checker(state_for_checker, app_for_checker));
-            
+
             true
         }
-        Err(_) => false,
+        None => false,
     }
 }
 
+/// Build a notify watcher for `folder` whose events feed [`handle_file_event`].
+/// Returns `None` if the watcher can't be created or can't attach to the folder.
+fn create_watcher(state: &SharedState, app: &AppHandle, folder: &str, recursive: bool) -> Option<RecommendedWatcher> {
+    let state_clone = Arc::clone(state);
+    let app_handle = app.clone();
+    let mut watcher = RecommendedWatcher::new(
+        move |res: Result<Event, notify::Error>| {
+            if let Ok(event) = res {
+                handle_file_event(event, &state_clone, &app_handle);
+            }
+        },
+        Config::default(),
+    ).ok()?;
+
+    let mode = if recursive { RecursiveMode::Recursive } else { RecursiveMode::NonRecursive };
+    watcher.watch(folder.as_ref(), mode).ok()?;
+    Some(watcher)
+}
+
 #[tauri::command]
 fn stop_monitor(state: tauri::State<SharedState>) -> bool {
     let mut s = state.lock().unwrap();
@@ -226,6 +414,7 @@ fn stop_monitor(state: tauri::State<SharedState>) -> bool {
     s.start_time.clear();
     s.dir_count = 0;
     s.folder_trackers.clear();
+    s.pending_renames.clear();
     true
 }
 
@@ -256,6 +445,95 @@ fn sign_all_batches(state: tauri::State<SharedState>) {
     save_history(&s.batches);
 }
 
+#[tauri::command]
+fn upload_batch(app: AppHandle, state: tauri::State<SharedState>, batch_id: String) -> bool {
+    spawn_upload(app, Arc::clone(state.inner()), batch_id)
+}
+
+#[tauri::command]
+fn retry_upload(app: AppHandle, state: tauri::State<SharedState>, batch_id: String) -> bool {
+    spawn_upload(app, Arc::clone(state.inner()), batch_id)
+}
+
+/// Lazily build (and cache) the S3 client, or `None` when upload is disabled or
+/// misconfigured.
+fn ensure_s3_client(s: &mut GlobalState) -> Option<S3Client> {
+    if let Some(client) = &s.s3_client {
+        return Some(client.clone());
+    }
+    if s.config.s3_bucket.is_empty() {
+        return None;
+    }
+    let client = upload::build_client(
+        &s.config.s3_endpoint,
+        &s.config.s3_region,
+        &s.config.s3_access_key,
+        &s.config.s3_secret_key,
+    )?;
+    s.s3_client = Some(client.clone());
+    Some(client)
+}
+
+/// Kick off a background upload for a batch that has reached `Signed` (or
+/// `Uploaded`, for a retry). Returns whether the upload was started.
+fn spawn_upload(app: AppHandle, state: SharedState, batch_id: String) -> bool {
+    let (client, bucket, prefix, folder, files) = {
+        let mut s = state.lock().unwrap();
+        let batch = match s.batches.iter().find(|b| b.id == batch_id) {
+            Some(b) => b.clone(),
+            None => return false,
+        };
+        if batch.status != BatchStatus::Signed && batch.status != BatchStatus::Uploaded {
+            return false;
+        }
+        let client = match ensure_s3_client(&mut s) {
+            Some(c) => c,
+            None => return false,
+        };
+        (client, s.config.s3_bucket.clone(), s.config.s3_prefix.clone(), batch.folder, batch.files)
+    };
+
+    tauri::async_runtime::spawn(async move {
+        let total = files.len();
+        let mut success = true;
+        for (i, file) in files.iter().enumerate() {
+            let path = PathBuf::from(&folder).join(file);
+            let key = upload::object_key(&prefix, &batch_id, file);
+            let ok = upload::put_file(&client, &bucket, &key, &path).await;
+            success &= ok;
+            app.emit("upload-progress", upload::UploadProgress {
+                batch_id: batch_id.clone(),
+                file: file.clone(),
+                uploaded: i + 1,
+                total,
+                ok,
+            }).ok();
+        }
+
+        if success {
+            let mut s = state.lock().unwrap();
+            let now = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+            for batch in &mut s.batches {
+                if batch.id == batch_id {
+                    batch.status = BatchStatus::Uploaded;
+                    batch.uploaded_at = Some(now.clone());
+                    break;
+                }
+            }
+            if s.config.save_history {
+                save_history(&s.batches);
+            }
+        }
+
+        app.emit("upload-batch-finished", upload::UploadResult {
+            batch_id: batch_id.clone(),
+            success,
+        }).ok();
+    });
+
+    true
+}
+
 #[tauri::command]
 fn clear_batches(state: tauri::State<SharedState>) {
     let mut s = state.lock().unwrap();
@@ -270,118 +548,454 @@ fn clear_all_batches(state: tauri::State<SharedState>) {
     save_history(&s.batches);
 }
 
-fn handle_file_event(event: Event, state: &SharedState, config: &AppConfig, app: &AppHandle) {
-    match event.kind {
-        EventKind::Create(_) | EventKind::Modify(_) => {},
-        _ => return,
+/// One item surfaced by the parallel baseline walk, sent over a channel to a
+/// single draining receiver so tree traversal never blocks on the state lock.
+enum ScanItem {
+    Dir,
+    File { folder: String, name: String },
+}
+
+/// Walk `root` in parallel (one worker per core) before the watcher goes live,
+/// honouring the same include/exclude `overrides`, to count directories and
+/// seed trackers for files already present. Results stream through a channel to
+/// a receiver thread that updates `GlobalState` as they arrive and emits a
+/// `scan-progress` event (the running directory count) whenever that count is
+/// a multiple of 64, so large trees don't freeze the UI; a final
+/// `scan-complete` carries the directory total.
+fn baseline_scan(state: SharedState, app: AppHandle, root: String, overrides: Override, recursive: bool) {
+    use std::sync::mpsc;
+
+    let (tx, rx) = mpsc::channel::<ScanItem>();
+
+    let rx_state = Arc::clone(&state);
+    let rx_app = app.clone();
+    let receiver = std::thread::spawn(move || {
+        let mut dir_count = 0i32;
+        for item in rx {
+            let mut s = rx_state.lock().unwrap();
+            if !s.is_running { break; }
+            match item {
+                ScanItem::Dir => {
+                    dir_count += 1;
+                    s.dir_count = dir_count;
+                }
+                ScanItem::File { folder, name } => {
+                    let now = Instant::now();
+                    let now_str = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+                    let started = register_file(&mut s, &folder, &name, now, &now_str);
+                    if started {
+                        drop(s);
+                        rx_app.emit("upload-started", &folder).ok();
+                        continue;
+                    }
+                }
+            }
+            drop(s);
+            if dir_count % 64 == 0 {
+                rx_app.emit("scan-progress", dir_count).ok();
+            }
+        }
+        rx_app.emit("scan-complete", dir_count).ok();
+    });
+
+    let threads = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4);
+    let mut builder = WalkBuilder::new(&root);
+    builder.standard_filters(false).hidden(false).threads(threads);
+    // Let the walker apply the same include/exclude rules itself, so excluded
+    // subtrees are pruned (never descended into, never counted) and only
+    // whitelisted files are yielded — the closure just forwards what survives.
+    builder.overrides(overrides);
+    if !recursive {
+        builder.max_depth(Some(1));
     }
-    
-    for path in event.paths {
-        if path.is_dir() { continue; }
-        
-        let ext = match path.extension() {
-            Some(e) => format!(".{}", e.to_string_lossy().to_lowercase()),
-            None => continue,
-        };
-        
-        if !config.file_types.is_empty() && !config.file_types.contains(&ext) {
-            continue;
+
+    builder.build_parallel().run(|| {
+        let tx = tx.clone();
+        Box::new(move |result| {
+            if let Ok(entry) = result {
+                let path = entry.path();
+                if entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+                    tx.send(ScanItem::Dir).ok();
+                } else if let (Some(parent), Some(name)) = (path.parent(), path.file_name()) {
+                    tx.send(ScanItem::File {
+                        folder: parent.to_string_lossy().to_string(),
+                        name: name.to_string_lossy().to_string(),
+                    }).ok();
+                }
+            }
+            WalkState::Continue
+        })
+    });
+
+    drop(tx);
+    receiver.join().ok();
+}
+
+fn handle_file_event(event: Event, state: &SharedState, app: &AppHandle) {
+    use notify::event::{ModifyKind, RenameMode};
+
+    match event.kind {
+        // New or growing files: add them to their folder's batch.
+        EventKind::Create(_)
+        | EventKind::Modify(ModifyKind::Data(_))
+        | EventKind::Modify(ModifyKind::Any) => {
+            for path in &event.paths {
+                add_path(path, state, app);
+            }
         }
-        
-        let path_str = path.to_string_lossy().to_string();
-        if config.ignore_folders.iter().any(|ig| path_str.contains(&format!("/{}/", ig)) || path_str.contains(&format!("\\{}\\", ig))) {
-            continue;
+        // A rename delivered as a single event carries [from, to]: swap in place
+        // so the batch keeps its identity.
+        EventKind::Modify(ModifyKind::Name(RenameMode::Both)) if event.paths.len() == 2 => {
+            handle_rename(&event.paths[0], &event.paths[1], state, app);
         }
-        
-        let folder = match path.parent() {
-            Some(p) => p.to_string_lossy().to_string(),
-            None => continue,
-        };
-        
-        let file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
-        
-        let mut s = state.lock().unwrap();
-        let now = Instant::now();
-        let now_str = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
-        
-        let need_notify_start;
-        let batch_id_for_update;
-        
-        if let Some(tracker) = s.folder_trackers.get_mut(&folder) {
-            if !tracker.files.contains(&file_name) {
-                tracker.files.push(file_name.clone());
+        // Split rename (old name): hold it against its tracker cookie so the
+        // following `To` can swap in place. Without a cookie to pair on, fall
+        // back to treating it as a removal.
+        EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
+            let cookie = event.attrs.tracker();
+            for path in &event.paths {
+                match cookie {
+                    Some(c) => record_pending_rename(state, c, path),
+                    None => remove_path(path, state, app),
+                }
             }
-            tracker.last_activity = now;
-            need_notify_start = !tracker.notified_start;
-            if need_notify_start { tracker.notified_start = true; }
-            batch_id_for_update = tracker.batch_id.clone();
-        } else {
-            let batch_id = Uuid::new_v4().to_string();
-            let tracker = FolderTracker {
-                files: vec![file_name.clone()],
-                last_activity: now,
-                started_at: now_str.clone(),
-                notified_start: true,
-                batch_id: batch_id.clone(),
-            };
-            s.folder_trackers.insert(folder.clone(), tracker);
-            
-            let batch = Batch {
-                id: batch_id.clone(),
-                folder: folder.clone(),
-                files: vec![file_name.clone()],
-                status: BatchStatus::Uploading,
-                started_at: now_str,
-                completed_at: None,
-                signed_at: None,
-                file_count: 1,
-            };
-            s.batches.insert(0, batch);
-            if s.batches.len() > 100 { s.batches.truncate(100); }
-            
-            need_notify_start = true;
-            batch_id_for_update = batch_id;
         }
-        
-        // Update batch files
-        let files_clone: Vec<String>;
-        if let Some(tracker) = s.folder_trackers.get(&folder) {
-            files_clone = tracker.files.clone();
-        } else {
-            continue;
+        // Split rename (new name): if it pairs with a held `From`, apply the
+        // rename; otherwise it's a plain addition.
+        EventKind::Modify(ModifyKind::Name(RenameMode::To))
+        | EventKind::Modify(ModifyKind::Name(RenameMode::Any)) => {
+            let cookie = event.attrs.tracker();
+            for path in &event.paths {
+                let pending = cookie.and_then(|c| take_pending_rename(state, c));
+                match pending {
+                    Some(p) => apply_rename(&p, path, state, app),
+                    None => add_path(path, state, app),
+                }
+            }
+        }
+        // Deletion: drop the file from its batch.
+        EventKind::Remove(_) => {
+            for path in &event.paths {
+                remove_path(path, state, app);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Stash a `From` side of a split rename, keyed by its tracker cookie.
+fn record_pending_rename(state: &SharedState, cookie: usize, path: &std::path::Path) {
+    let folder = match path.parent() {
+        Some(p) => p.to_string_lossy().to_string(),
+        None => return,
+    };
+    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    state.lock().unwrap().pending_renames.insert(cookie, PendingRename {
+        folder,
+        name,
+        at: Instant::now(),
+    });
+}
+
+/// Take back a held `From` side by cookie, if still pending.
+fn take_pending_rename(state: &SharedState, cookie: usize) -> Option<PendingRename> {
+    state.lock().unwrap().pending_renames.remove(&cookie)
+}
+
+/// Apply a paired split rename: swap in place when the file stays in its folder,
+/// otherwise remove it from the old folder and add it to the new one.
+fn apply_rename(pending: &PendingRename, to: &std::path::Path, state: &SharedState, app: &AppHandle) {
+    let to_folder = to.parent().map(|p| p.to_string_lossy().to_string());
+    if to_folder.as_deref() == Some(pending.folder.as_str()) {
+        let new_name = to.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let mut s = state.lock().unwrap();
+        rename_file(&mut s, &pending.folder, &pending.name, &new_name);
+        return;
+    }
+
+    let mut s = state.lock().unwrap();
+    let batch_removed = remove_file(&mut s, &pending.folder, &pending.name);
+    drop(s);
+    if batch_removed {
+        app.emit("upload-removed", &pending.folder).ok();
+    }
+    add_path(to, state, app);
+}
+
+/// Register a single created/modified file against its folder's batch.
+fn add_path(path: &std::path::Path, state: &SharedState, app: &AppHandle) {
+    if path.is_dir() { return; }
+
+    let folder = match path.parent() {
+        Some(p) => p.to_string_lossy().to_string(),
+        None => return,
+    };
+    let file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+
+    let mut s = state.lock().unwrap();
+
+    // Gitignore-style filtering: skip anything the compiled rule set excludes
+    // (or fails to whitelist when includes are present).
+    if s.overrides.matched(path, false).is_ignore() {
+        return;
+    }
+
+    let now = Instant::now();
+    let now_str = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    let need_notify_start = register_file(&mut s, &folder, &file_name, now, &now_str);
+
+    drop(s);
+
+    if need_notify_start {
+        app.emit("upload-started", &folder).ok();
+    }
+}
+
+/// Drop a removed/moved-out file from its folder's batch. If that empties the
+/// batch, tear down the tracker and remove the batch rather than leaving a
+/// zero-file `Uploading` entry.
+fn remove_path(path: &std::path::Path, state: &SharedState, app: &AppHandle) {
+    let folder = match path.parent() {
+        Some(p) => p.to_string_lossy().to_string(),
+        None => return,
+    };
+    let file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+
+    let mut s = state.lock().unwrap();
+    let batch_removed = remove_file(&mut s, &folder, &file_name);
+    drop(s);
+
+    if batch_removed {
+        app.emit("upload-removed", &folder).ok();
+    }
+}
+
+/// Handle a whole-in-one rename event. A rename inside the same folder swaps the
+/// name in place, preserving the batch; a move across folders is a removal from
+/// the old folder plus an addition to the new one.
+fn handle_rename(from: &std::path::Path, to: &std::path::Path, state: &SharedState, app: &AppHandle) {
+    let from_folder = from.parent().map(|p| p.to_string_lossy().to_string());
+    let to_folder = to.parent().map(|p| p.to_string_lossy().to_string());
+
+    if let (Some(ff), Some(tf)) = (&from_folder, &to_folder) {
+        if ff == tf {
+            let old_name = from.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            let new_name = to.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            let mut s = state.lock().unwrap();
+            rename_file(&mut s, ff, &old_name, &new_name);
+            return;
+        }
+    }
+
+    remove_path(from, state, app);
+    add_path(to, state, app);
+}
+
+/// Remove `file_name` from its folder's tracker and batch. Returns `true` when
+/// the batch went empty and was dropped.
+fn remove_file(s: &mut GlobalState, folder: &str, file_name: &str) -> bool {
+    let (batch_id, now_empty) = match s.folder_trackers.get_mut(folder) {
+        Some(tracker) => {
+            tracker.files.retain(|f| f != file_name);
+            (tracker.batch_id.clone(), tracker.files.is_empty())
         }
-        
-        for batch in &mut s.batches {
-            if batch.id == batch_id_for_update {
-                batch.files = files_clone.clone();
-                batch.file_count = files_clone.len();
-                break;
+        None => return false,
+    };
+
+    if now_empty {
+        s.folder_trackers.remove(folder);
+        s.batches.retain(|b| b.id != batch_id);
+        return true;
+    }
+
+    let files = s.folder_trackers.get(folder).map(|t| t.files.clone()).unwrap_or_default();
+    for batch in &mut s.batches {
+        if batch.id == batch_id {
+            batch.files = files.clone();
+            batch.file_count = files.len();
+            break;
+        }
+    }
+    false
+}
+
+/// Swap `old_name` for `new_name` within a folder's tracker and batch, keeping
+/// the batch identity. If `old_name` isn't tracked, `new_name` is appended
+/// (when not already present), so the file count grows by one.
+fn rename_file(s: &mut GlobalState, folder: &str, old_name: &str, new_name: &str) {
+    let batch_id = match s.folder_trackers.get_mut(folder) {
+        Some(tracker) => {
+            if let Some(pos) = tracker.files.iter().position(|f| f == old_name) {
+                tracker.files[pos] = new_name.to_string();
+            } else if !tracker.files.iter().any(|f| f == new_name) {
+                tracker.files.push(new_name.to_string());
             }
+            tracker.batch_id.clone()
         }
-        
-        drop(s);
-        
-        if need_notify_start {
-            app.emit("upload-started", &folder).ok();
+        None => return,
+    };
+
+    let files = s.folder_trackers.get(folder).map(|t| t.files.clone()).unwrap_or_default();
+    for batch in &mut s.batches {
+        if batch.id == batch_id {
+            batch.files = files.clone();
+            batch.file_count = files.len();
+            break;
         }
     }
 }
 
+/// Record `file_name` against its `folder`, creating the folder's tracker and
+/// its `Uploading` batch on first sight and appending to both otherwise. Keeps
+/// the batch's `files`/`file_count` in sync with the tracker. Returns whether
+/// this folder's upload just started (caller emits `upload-started`).
+fn register_file(s: &mut GlobalState, folder: &str, file_name: &str, now: Instant, now_str: &str) -> bool {
+    let need_notify_start;
+    let batch_id_for_update;
+
+    if let Some(tracker) = s.folder_trackers.get_mut(folder) {
+        if !tracker.files.iter().any(|f| f == file_name) {
+            tracker.files.push(file_name.to_string());
+        }
+        tracker.last_activity = now;
+        need_notify_start = !tracker.notified_start;
+        if need_notify_start { tracker.notified_start = true; }
+        batch_id_for_update = tracker.batch_id.clone();
+    } else {
+        let batch_id = Uuid::new_v4().to_string();
+        let tracker = FolderTracker {
+            files: vec![file_name.to_string()],
+            last_activity: now,
+            started_at: now_str.to_string(),
+            notified_start: true,
+            batch_id: batch_id.clone(),
+            file_stats: HashMap::new(),
+            stable_cycles: 0,
+            activity_at_last_poll: now,
+        };
+        s.folder_trackers.insert(folder.to_string(), tracker);
+
+        let batch = Batch {
+            id: batch_id.clone(),
+            folder: folder.to_string(),
+            files: vec![file_name.to_string()],
+            status: BatchStatus::Uploading,
+            started_at: now_str.to_string(),
+            completed_at: None,
+            signed_at: None,
+            uploaded_at: None,
+            file_count: 1,
+        };
+        s.batches.insert(0, batch);
+        if s.batches.len() > 100 { s.batches.truncate(100); }
+
+        need_notify_start = true;
+        batch_id_for_update = batch_id;
+    }
+
+    // Keep the batch's file list in sync with the tracker.
+    let files_clone = match s.folder_trackers.get(folder) {
+        Some(t) => t.files.clone(),
+        None => return need_notify_start,
+    };
+    for batch in &mut s.batches {
+        if batch.id == batch_id_for_update {
+            batch.files = files_clone.clone();
+            batch.file_count = files_clone.len();
+            break;
+        }
+    }
+
+    need_notify_start
+}
+
+/// Stat a file into a `(size, mtime_secs)` pair for stability comparison.
+fn file_stat(path: &std::path::Path) -> Option<(u64, i64)> {
+    let md = fs::metadata(path).ok()?;
+    let mtime = md.modified().ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    Some((md.len(), mtime))
+}
+
 fn completion_checker(state: SharedState, app: AppHandle) {
     let timeout = Duration::from_secs(UPLOAD_COMPLETE_TIMEOUT);
-    
+
     loop {
-        std::thread::sleep(Duration::from_secs(1));
-        
+        let poll = {
+            let s = state.lock().unwrap();
+            if !s.is_running { break; }
+            s.config.poll_interval_secs.max(1)
+        };
+        std::thread::sleep(Duration::from_secs(poll));
+
         let mut s = state.lock().unwrap();
         if !s.is_running { break; }
-        
+
         let now = Instant::now();
-        let completed_folders: Vec<String> = s.folder_trackers.iter()
-            .filter(|(_, t)| now.duration_since(t.last_activity) >= timeout)
-            .map(|(f, _)| f.clone())
+        let needed = s.config.stability_cycles.max(1);
+
+        // A held `From` whose matching `To` never arrived is a genuine
+        // move-out: flush it as a removal once its grace window lapses.
+        let rename_grace = Duration::from_secs(2);
+        let expired: Vec<usize> = s.pending_renames.iter()
+            .filter(|(_, p)| now.duration_since(p.at) >= rename_grace)
+            .map(|(c, _)| *c)
             .collect();
-        
+        let mut removed_folders: Vec<String> = Vec::new();
+        for cookie in expired {
+            if let Some(p) = s.pending_renames.remove(&cookie) {
+                if remove_file(&mut s, &p.folder, &p.name) {
+                    removed_folders.push(p.folder);
+                }
+            }
+        }
+        if !removed_folders.is_empty() {
+            drop(s);
+            for folder in &removed_folders {
+                app.emit("upload-removed", folder).ok();
+            }
+            s = state.lock().unwrap();
+        }
+
+        let folders: Vec<String> = s.folder_trackers.keys().cloned().collect();
+
+        // A folder completes once its files' size and mtime have been stable for
+        // `needed` consecutive cycles with no new events, or once the blind
+        // timeout is hit as an upper bound for stalled transfers.
+        let mut completed_folders: Vec<String> = Vec::new();
+        for folder in folders {
+            let files = match s.folder_trackers.get(&folder) {
+                Some(t) => t.files.clone(),
+                None => continue,
+            };
+            let mut snapshot = HashMap::with_capacity(files.len());
+            for file in &files {
+                if let Some(stat) = file_stat(&PathBuf::from(&folder).join(file)) {
+                    snapshot.insert(file.clone(), stat);
+                }
+            }
+
+            let tracker = match s.folder_trackers.get_mut(&folder) {
+                Some(t) => t,
+                None => continue,
+            };
+            let no_new_events = tracker.last_activity <= tracker.activity_at_last_poll;
+            let unchanged = !snapshot.is_empty() && snapshot == tracker.file_stats;
+            if unchanged && no_new_events {
+                tracker.stable_cycles += 1;
+            } else {
+                tracker.stable_cycles = 0;
+                tracker.file_stats = snapshot;
+            }
+            tracker.activity_at_last_poll = now;
+
+            if tracker.stable_cycles >= needed || now.duration_since(tracker.last_activity) >= timeout {
+                completed_folders.push(folder);
+            }
+        }
+
         for folder in completed_folders {
             if let Some(tracker) = s.folder_trackers.remove(&folder) {
                 let completed_time = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
@@ -394,27 +1008,73 @@ fn completion_checker(state: SharedState, app: AppHandle) {
                         break;
                     }
                 }
-                
+
                 if s.config.save_history {
                     save_history(&s.batches);
                 }
-                
+
+                let batch_id = tracker.batch_id.clone();
+                let files = tracker.files.clone();
                 let folder_clone = folder.clone();
                 drop(s);
                 app.emit("upload-completed", &folder_clone).ok();
+                check_duplicates(&state, &app, &batch_id, &folder_clone, &files);
                 s = state.lock().unwrap();
             }
         }
     }
 }
 
+/// Payload for the `duplicate-detected` event: the batch that just completed and
+/// the ids of earlier batches whose footage it appears to repeat.
+#[derive(Debug, Clone, Serialize)]
+struct DuplicateEvent {
+    batch_id: String,
+    duplicate_batch_ids: Vec<String>,
+}
+
+/// Fingerprint each video in a freshly-completed batch, check it against every
+/// earlier batch via the BK-tree, then index it. Emits `duplicate-detected`
+/// with the matching earlier batch ids when any video resurfaces, so operators
+/// don't re-sign footage they've already handled. Runs off the state lock while
+/// shelling out to ffmpeg.
+fn check_duplicates(state: &SharedState, app: &AppHandle, batch_id: &str, folder: &str, files: &[String]) {
+    let tolerance = state.lock().unwrap().config.duplicate_tolerance.min(20);
+
+    let mut matches: Vec<String> = Vec::new();
+    for file in files {
+        let path = PathBuf::from(folder).join(file);
+        let hash = match phash::video_hash(&path) {
+            Some(h) => h,
+            None => continue,
+        };
+
+        let mut s = state.lock().unwrap();
+        for id in s.dup_index.query(hash, tolerance) {
+            if id != batch_id && !matches.contains(&id) {
+                matches.push(id);
+            }
+        }
+        s.dup_index.insert(hash, batch_id.to_string());
+    }
+
+    if !matches.is_empty() {
+        app.emit("duplicate-detected", DuplicateEvent {
+            batch_id: batch_id.to_string(),
+            duplicate_batch_ids: matches,
+        }).ok();
+    }
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     let config = load_config();
     let batches = load_history();
-    
+    let overrides = build_overrides(&config);
+
     let state = Arc::new(Mutex::new(GlobalState {
         config,
+        overrides,
         batches,
         ..Default::default()
     }));
@@ -424,10 +1084,19 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_notification::init())
         .manage(state)
+        .setup(|app| {
+            // Watch the config file for live edits and hot-reload them.
+            let shared = Arc::clone(app.state::<SharedState>().inner());
+            let handle = app.handle().clone();
+            let watcher = start_config_watcher(Arc::clone(&shared), handle);
+            shared.lock().unwrap().config_watcher = watcher;
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             get_config, set_config, get_state,
             start_monitor, stop_monitor,
             sign_batch, sign_all_batches,
+            upload_batch, retry_upload,
             clear_batches, clear_all_batches,
         ])
         .run(tauri::generate_context!())
@@ -0,0 +1,242 @@
+//! Perceptual video fingerprinting and near-duplicate lookup.
+//!
+//! A video is reduced to a single 64-bit fingerprint: a handful of evenly
+//! spaced frames are extracted with `ffmpeg`, downscaled to 32×32 grayscale,
+//! reduced to a DCT-based perceptual hash, and combined by per-bit majority
+//! vote. Fingerprints are indexed in a [`BkTree`] keyed by Hamming distance so
+//! a newly completed batch can be checked against every earlier one in
+//! sub-linear time.
+
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::path::Path;
+use std::process::Command;
+
+/// Number of frames sampled per video.
+const FRAME_SAMPLES: usize = 8;
+/// Frame side length fed to the DCT.
+const FRAME_SIZE: usize = 32;
+
+/// Hamming distance between two fingerprints.
+#[inline]
+fn hamming(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+/// A BK-tree over 64-bit fingerprints, each carrying the id of the batch it
+/// came from. Children are indexed by their integer Hamming distance to the
+/// parent, which is what makes bounded-radius queries prune most of the tree.
+#[derive(Default)]
+pub struct BkTree {
+    root: Option<Box<Node>>,
+}
+
+struct Node {
+    hash: u64,
+    id: String,
+    children: HashMap<u32, Box<Node>>,
+}
+
+impl Node {
+    fn insert(&mut self, hash: u64, id: String) {
+        let d = hamming(self.hash, hash);
+        match self.children.get_mut(&d) {
+            Some(child) => child.insert(hash, id),
+            None => {
+                self.children.insert(d, Box::new(Node { hash, id, children: HashMap::new() }));
+            }
+        }
+    }
+
+    fn query(&self, hash: u64, radius: u32, out: &mut Vec<String>) {
+        let d = hamming(self.hash, hash);
+        if d <= radius {
+            out.push(self.id.clone());
+        }
+        let lo = d.saturating_sub(radius);
+        let hi = d.saturating_add(radius);
+        for (&edge, child) in &self.children {
+            if edge >= lo && edge <= hi {
+                child.query(hash, radius, out);
+            }
+        }
+    }
+}
+
+impl BkTree {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Index `hash` under the batch `id`.
+    pub fn insert(&mut self, hash: u64, id: String) {
+        match &mut self.root {
+            Some(root) => root.insert(hash, id),
+            None => self.root = Some(Box::new(Node { hash, id, children: HashMap::new() })),
+        }
+    }
+
+    /// Return the ids of every indexed fingerprint within `radius` of `hash`.
+    pub fn query(&self, hash: u64, radius: u32) -> Vec<String> {
+        let mut out = Vec::new();
+        if let Some(root) = &self.root {
+            root.query(hash, radius, &mut out);
+        }
+        out
+    }
+}
+
+/// Fingerprint a video, or `None` if `ffmpeg`/`ffprobe` are unavailable or the
+/// file yields no decodable frames.
+pub fn video_hash(path: &Path) -> Option<u64> {
+    let duration = probe_duration(path)?;
+
+    let mut frames: Vec<u64> = Vec::with_capacity(FRAME_SAMPLES);
+    for i in 0..FRAME_SAMPLES {
+        // Sample the midpoint of each of FRAME_SAMPLES equal slices.
+        let ts = duration * (i as f64 + 0.5) / FRAME_SAMPLES as f64;
+        if let Some(frame) = extract_frame(path, ts) {
+            frames.push(frame_hash(&frame));
+        }
+    }
+
+    if frames.is_empty() {
+        return None;
+    }
+    Some(combine(&frames))
+}
+
+/// Combine per-frame hashes into one by majority vote on each bit.
+fn combine(frames: &[u64]) -> u64 {
+    let threshold = frames.len() / 2;
+    let mut out = 0u64;
+    for bit in 0..64 {
+        let ones = frames.iter().filter(|h| (*h >> bit) & 1 == 1).count();
+        if ones > threshold {
+            out |= 1 << bit;
+        }
+    }
+    out
+}
+
+/// Probe a video's duration in seconds via `ffprobe`.
+fn probe_duration(path: &Path) -> Option<f64> {
+    let out = Command::new("ffprobe")
+        .args(["-v", "error", "-show_entries", "format=duration", "-of", "default=nw=1:nk=1"])
+        .arg(path)
+        .output()
+        .ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&out.stdout).trim().parse::<f64>().ok().filter(|d| *d > 0.0)
+}
+
+/// Extract a single frame at `ts` seconds as a 32×32 grayscale buffer.
+fn extract_frame(path: &Path, ts: f64) -> Option<[f64; FRAME_SIZE * FRAME_SIZE]> {
+    let out = Command::new("ffmpeg")
+        .args(["-v", "error", "-ss", &format!("{ts:.3}"), "-i"])
+        .arg(path)
+        .args([
+            "-frames:v", "1",
+            "-vf", &format!("scale={FRAME_SIZE}:{FRAME_SIZE},format=gray"),
+            "-f", "rawvideo", "-",
+        ])
+        .output()
+        .ok()?;
+    if !out.status.success() || out.stdout.len() < FRAME_SIZE * FRAME_SIZE {
+        return None;
+    }
+    let mut buf = [0f64; FRAME_SIZE * FRAME_SIZE];
+    for (dst, &src) in buf.iter_mut().zip(out.stdout.iter()) {
+        *dst = src as f64;
+    }
+    Some(buf)
+}
+
+/// DCT-based perceptual hash of a single 32×32 grayscale frame: take the 2D
+/// DCT, keep the top-left 8×8 low-frequency block, and set each of 64 bits to 1
+/// where the coefficient exceeds the median of the block's non-DC terms.
+fn frame_hash(pixels: &[f64; FRAME_SIZE * FRAME_SIZE]) -> u64 {
+    let n = FRAME_SIZE;
+
+    // cos[(2x+1) * u * PI / 2N], reused across rows and columns.
+    let mut cos = [[0f64; 8]; FRAME_SIZE];
+    for (x, row) in cos.iter_mut().enumerate() {
+        for (u, c) in row.iter_mut().enumerate() {
+            *c = (((2 * x + 1) as f64) * (u as f64) * PI / (2.0 * n as f64)).cos();
+        }
+    }
+    let alpha = |u: usize| if u == 0 { (1.0 / n as f64).sqrt() } else { (2.0 / n as f64).sqrt() };
+
+    let mut coeffs = [0f64; 64];
+    for u in 0..8 {
+        for v in 0..8 {
+            let mut sum = 0.0;
+            for x in 0..n {
+                for y in 0..n {
+                    sum += pixels[x * n + y] * cos[x][u] * cos[y][v];
+                }
+            }
+            coeffs[u * 8 + v] = alpha(u) * alpha(v) * sum;
+        }
+    }
+
+    // Median over the low-frequency block excluding the DC term, which would
+    // otherwise dominate and flatten the fingerprint.
+    let mut sorted: Vec<f64> = coeffs[1..].to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let median = sorted[sorted.len() / 2];
+
+    let mut hash = 0u64;
+    for (i, &c) in coeffs.iter().enumerate() {
+        if c > median {
+            hash |= 1 << i;
+        }
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hamming_counts_differing_bits() {
+        assert_eq!(hamming(0, 0), 0);
+        assert_eq!(hamming(0b1010, 0b0101), 4);
+        assert_eq!(hamming(u64::MAX, 0), 64);
+    }
+
+    #[test]
+    fn combine_is_per_bit_majority() {
+        // bit 0 is set in 2 of 3 frames (kept); bit 1 in only 1 (dropped).
+        assert_eq!(combine(&[0b01, 0b01, 0b10]), 0b01);
+    }
+
+    #[test]
+    fn bktree_query_reports_everything_within_radius() {
+        let mut tree = BkTree::new();
+        tree.insert(0b0000, "a".into()); // distance 0 from the probe
+        tree.insert(0b0011, "b".into()); // distance 2
+        tree.insert(0b1111, "c".into()); // distance 4
+
+        let mut got = tree.query(0b0000, 2);
+        got.sort();
+        assert_eq!(got, vec!["a".to_string(), "b".to_string()]);
+    }
+
+    #[test]
+    fn bktree_radius_zero_is_an_exact_match() {
+        let mut tree = BkTree::new();
+        tree.insert(42, "x".into());
+        tree.insert(43, "y".into());
+        assert_eq!(tree.query(42, 0), vec!["x".to_string()]);
+    }
+
+    #[test]
+    fn bktree_query_on_empty_tree_is_empty() {
+        let tree = BkTree::new();
+        assert!(tree.query(7, 5).is_empty());
+    }
+}
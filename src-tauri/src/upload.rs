@@ -0,0 +1,95 @@
+//! Object-storage archival of signed batches.
+//!
+//! A single S3 client is built lazily from the configured credentials and
+//! reused across uploads. Each batch's files are streamed to the bucket in the
+//! background, emitting per-file `upload-progress` events so the UI can track a
+//! batch through the last leg of its lifecycle: watch → complete → sign →
+//! archive to cloud.
+
+use aws_sdk_s3::config::{BehaviorVersion, Credentials, Region};
+use aws_sdk_s3::primitives::ByteStream;
+use aws_sdk_s3::Client;
+use serde::Serialize;
+use std::path::Path;
+
+/// Progress for one file within a batch upload.
+#[derive(Debug, Clone, Serialize)]
+pub struct UploadProgress {
+    pub batch_id: String,
+    pub file: String,
+    pub uploaded: usize,
+    pub total: usize,
+    pub ok: bool,
+}
+
+/// Outcome of a whole-batch upload.
+#[derive(Debug, Clone, Serialize)]
+pub struct UploadResult {
+    pub batch_id: String,
+    pub success: bool,
+}
+
+/// Build an S3-compatible client from explicit credentials. A custom endpoint
+/// (e.g. MinIO) switches on path-style addressing; an empty endpoint falls back
+/// to AWS's virtual-hosted style. Returns `None` only when the region is blank.
+pub fn build_client(endpoint: &str, region: &str, access_key: &str, secret_key: &str) -> Option<Client> {
+    if region.is_empty() {
+        return None;
+    }
+    let creds = Credentials::new(access_key, secret_key, None, None, "fidruawatch");
+    let mut builder = aws_sdk_s3::Config::builder()
+        .behavior_version(BehaviorVersion::latest())
+        .region(Region::new(region.to_string()))
+        .credentials_provider(creds);
+    if !endpoint.is_empty() {
+        builder = builder.endpoint_url(endpoint).force_path_style(true);
+    }
+    Some(Client::from_conf(builder.build()))
+}
+
+/// Stream a single file to `bucket` under `key`. Returns whether it landed.
+pub async fn put_file(client: &Client, bucket: &str, key: &str, path: &Path) -> bool {
+    let body = match ByteStream::from_path(path).await {
+        Ok(b) => b,
+        Err(_) => return false,
+    };
+    client
+        .put_object()
+        .bucket(bucket)
+        .key(key)
+        .body(body)
+        .send()
+        .await
+        .is_ok()
+}
+
+/// Object key for a file, namespaced by the optional prefix and the batch id so
+/// re-uploads of the same footage don't collide.
+pub fn object_key(prefix: &str, batch_id: &str, file: &str) -> String {
+    let prefix = prefix.trim_matches('/');
+    if prefix.is_empty() {
+        format!("{batch_id}/{file}")
+    } else {
+        format!("{prefix}/{batch_id}/{file}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::object_key;
+
+    #[test]
+    fn object_key_without_prefix() {
+        assert_eq!(object_key("", "batch1", "clip.mp4"), "batch1/clip.mp4");
+    }
+
+    #[test]
+    fn object_key_with_prefix() {
+        assert_eq!(object_key("footage", "batch1", "clip.mp4"), "footage/batch1/clip.mp4");
+    }
+
+    #[test]
+    fn object_key_trims_surrounding_slashes() {
+        assert_eq!(object_key("/footage/", "batch1", "clip.mp4"), "footage/batch1/clip.mp4");
+    }
+}